@@ -1,15 +1,26 @@
 use anyhow::{Context, Result};
-use crossterm::event::{KeyCode, KeyEvent};
-use std::{cmp, fs};
+use crossterm::event::{
+    DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEvent, KeyModifiers,
+};
+use ropey::Rope;
+use std::{
+    cmp,
+    collections::HashMap,
+    fs,
+    io::{self, BufReader},
+};
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Style},
-    text::Spans,
+    text::{Span, Spans},
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
 
+mod text_input;
+use text_input::TextInput;
+
 type Terminal = tui::Terminal<tui::backend::CrosstermBackend<std::io::Stdout>>;
 
 #[derive(PartialEq)]
@@ -19,6 +30,188 @@ enum UiState {
     SearchPrompt,
 }
 
+#[derive(PartialEq)]
+enum SearchDirection {
+    Forward,
+    Backward,
+}
+
+// A keymap entry: given the App, perform the bound action and report
+// whether the application should quit, exactly like `handle_*_key_event`.
+type Action = fn(&mut App) -> anyhow::Result<bool>;
+
+// Build the default main-mode keymap. Data-driven so user-defined bindings
+// loaded from a config file can slot in here later.
+fn load_actions() -> HashMap<(KeyCode, KeyModifiers), Action> {
+    let mut actions: HashMap<(KeyCode, KeyModifiers), Action> = HashMap::new();
+    let none = KeyModifiers::NONE;
+    actions.insert((KeyCode::Char('q'), none), quit);
+    actions.insert((KeyCode::Esc, none), quit);
+    actions.insert((KeyCode::Char('o'), none), open_file_prompt);
+    actions.insert((KeyCode::Char('s'), none), open_search_prompt);
+    actions.insert((KeyCode::Down, none), scroll_down);
+    actions.insert((KeyCode::Char(' '), none), scroll_down);
+    actions.insert((KeyCode::Up, none), scroll_up);
+    actions.insert((KeyCode::Char('n'), none), goto_next_match);
+    actions.insert((KeyCode::Char('N'), none), goto_prev_match);
+    actions.insert((KeyCode::Char('i'), none), toggle_case_insensitive);
+    actions.insert((KeyCode::Char('l'), none), toggle_gutter);
+    actions.insert((KeyCode::Char('e'), none), toggle_quit_at_eof);
+    actions.insert((KeyCode::Char(']'), none), next_file);
+    actions.insert((KeyCode::Char('['), none), prev_file);
+    actions.insert((KeyCode::Char('g'), none), goto_file_start);
+    actions.insert((KeyCode::Char('G'), none), goto_file_end);
+    actions.insert((KeyCode::PageDown, none), page_down);
+    actions.insert((KeyCode::PageUp, none), page_up);
+    actions.insert((KeyCode::Char('d'), KeyModifiers::CONTROL), half_page_down);
+    actions.insert((KeyCode::Char('u'), KeyModifiers::CONTROL), half_page_up);
+    actions
+}
+
+fn quit(_app: &mut App) -> anyhow::Result<bool> {
+    Ok(true)
+}
+
+fn open_file_prompt(app: &mut App) -> anyhow::Result<bool> {
+    app.state = UiState::FilePrompt;
+    app.input = TextInput::new()
+        .with_filter(reject_control_chars)
+        .with_validate(validate_file_path);
+    app.log = prompt_string(&app.state);
+    Ok(false)
+}
+
+fn open_search_prompt(app: &mut App) -> anyhow::Result<bool> {
+    app.state = UiState::SearchPrompt;
+    app.input = TextInput::new().with_filter(reject_control_chars);
+    app.log = prompt_string(&app.state);
+    Ok(false)
+}
+
+fn reject_control_chars(c: char) -> Option<char> {
+    if c.is_control() {
+        None
+    } else {
+        Some(c)
+    }
+}
+
+// A FilePrompt is only accepted once the path can actually be opened for
+// reading, so a typo surfaces in the log instead of silently replacing the
+// current file with an error placeholder.
+fn validate_file_path(path: &str) -> Result<(), String> {
+    fs::File::open(path)
+        .map(|_| ())
+        .map_err(|e| format!("cannot open {}: {}", path, e))
+}
+
+fn scroll_down(app: &mut App) -> anyhow::Result<bool> {
+    if app.quit_at_eof && app.at_eof {
+        return Ok(true);
+    }
+    app.cur += 1;
+    app.log = "Got KeyCode Down".to_string();
+    Ok(false)
+}
+
+fn scroll_up(app: &mut App) -> anyhow::Result<bool> {
+    if app.cur > 0 {
+        app.cur -= 1;
+    }
+    app.log = "Got KeyCode Up".to_string();
+    Ok(false)
+}
+
+fn goto_next_match(app: &mut App) -> anyhow::Result<bool> {
+    app.search_direction = SearchDirection::Forward;
+    app.advance_match();
+    Ok(false)
+}
+
+fn goto_prev_match(app: &mut App) -> anyhow::Result<bool> {
+    app.search_direction = SearchDirection::Backward;
+    app.advance_match();
+    Ok(false)
+}
+
+fn toggle_case_insensitive(app: &mut App) -> anyhow::Result<bool> {
+    app.case_insensitive = !app.case_insensitive;
+    app.update_matches();
+    app.log = format!("case-insensitive: {}", app.case_insensitive);
+    Ok(false)
+}
+
+fn toggle_gutter(app: &mut App) -> anyhow::Result<bool> {
+    app.show_gutter = !app.show_gutter;
+    app.log = format!("line numbers: {}", app.show_gutter);
+    Ok(false)
+}
+
+fn toggle_quit_at_eof(app: &mut App) -> anyhow::Result<bool> {
+    app.quit_at_eof = !app.quit_at_eof;
+    app.log = format!("quit at EOF: {}", app.quit_at_eof);
+    Ok(false)
+}
+
+fn next_file(app: &mut App) -> anyhow::Result<bool> {
+    if app.file_idx + 1 < app.files.len() {
+        app.file_idx += 1;
+        app.load_current_file();
+    } else {
+        app.log = "no next file".to_string();
+    }
+    Ok(false)
+}
+
+fn prev_file(app: &mut App) -> anyhow::Result<bool> {
+    if app.file_idx > 0 {
+        app.file_idx -= 1;
+        app.load_current_file();
+    } else {
+        app.log = "no previous file".to_string();
+    }
+    Ok(false)
+}
+
+// Jump to the very first line of the file.
+fn goto_file_start(app: &mut App) -> anyhow::Result<bool> {
+    app.cur = 0;
+    app.log = "Got KeyCode goto_file_start".to_string();
+    Ok(false)
+}
+
+// Jump so the last line of the file sits at the bottom of the Frame;
+// `main_ui` clamps `cur` to the highest valid scroll position on render.
+fn goto_file_end(app: &mut App) -> anyhow::Result<bool> {
+    app.cur = app.lines;
+    app.log = "Got KeyCode goto_file_end".to_string();
+    Ok(false)
+}
+
+fn half_page_down(app: &mut App) -> anyhow::Result<bool> {
+    app.cur += (app.height / 2).max(1);
+    app.log = "Got KeyCode half_page_down".to_string();
+    Ok(false)
+}
+
+fn half_page_up(app: &mut App) -> anyhow::Result<bool> {
+    app.cur = app.cur.saturating_sub((app.height / 2).max(1));
+    app.log = "Got KeyCode half_page_up".to_string();
+    Ok(false)
+}
+
+fn page_down(app: &mut App) -> anyhow::Result<bool> {
+    app.cur += app.height.max(1);
+    app.log = "Got KeyCode page_down".to_string();
+    Ok(false)
+}
+
+fn page_up(app: &mut App) -> anyhow::Result<bool> {
+    app.cur = app.cur.saturating_sub(app.height.max(1));
+    app.log = "Got KeyCode page_up".to_string();
+    Ok(false)
+}
+
 fn prompt_string(s: &UiState) -> String {
     match s {
         UiState::Main => String::from(""),
@@ -30,32 +223,52 @@ fn prompt_string(s: &UiState) -> String {
 //#[derive(Debug)]
 pub struct App {
     terminal: Terminal,
-    filename: String,
-    tmpbuf: String,
-    content: String,
+    files: Vec<String>,
+    file_idx: usize,
+    input: TextInput,
+    content: Rope,
     search: String,
+    case_insensitive: bool,
+    matches: Vec<(usize, usize, usize)>, // (line, byte col, byte len) of every occurrence of `search`
+    current_match: usize,
+    search_direction: SearchDirection,
+    show_gutter: bool,
+    at_eof: bool,
+    quit_at_eof: bool,
+    height: usize, // visible rows in the content frame, as of the last render
     lines: usize,
     log: String,
     cur: usize, // current position
     state: UiState,
+    actions: HashMap<(KeyCode, KeyModifiers), Action>,
 }
 
 impl App {
-    pub fn new(filename: String) -> Result<Self> {
-        let content = fs::read_to_string(&filename).context("could not read the file")?;
+    pub fn new(files: Vec<String>) -> Result<Self> {
+        let content = load_rope(&files[0]).context("could not read the file")?;
 
-        let lines = count_newlines(&content);
+        let lines = content_line_count(&content);
         let terminal = Self::setup_terminal()?;
         Ok(Self {
             terminal,
-            filename,
-            tmpbuf: String::from(""),
+            files,
+            file_idx: 0,
+            input: TextInput::new(),
             content,
             search: String::from(""),
+            case_insensitive: false,
+            matches: Vec::new(),
+            current_match: 0,
+            search_direction: SearchDirection::Forward,
+            show_gutter: false,
+            at_eof: false,
+            quit_at_eof: false,
+            height: 0,
             lines,
             log: String::from("<log text goes here>"),
             cur: 0,
             state: UiState::Main,
+            actions: load_actions(),
         })
     }
 
@@ -75,14 +288,18 @@ impl App {
     fn handle_event(&mut self) -> anyhow::Result<bool> {
         while crossterm::event::poll(std::time::Duration::from_secs(0))? {
             match crossterm::event::read()? {
-                crossterm::event::Event::Key(key) => {
+                Event::Key(key) => {
                     if self.handle_key_event(key)? {
                         return Ok(true);
                     }
                 }
-                crossterm::event::Event::Resize(_, _) => {
+                Event::Resize(_, _) => {
                     self.render_ui()?;
                 }
+                Event::Paste(text) if self.state != UiState::Main => {
+                    self.input.insert_str(&text);
+                    self.log = format!("{}{}", prompt_string(&self.state), self.input.value());
+                }
                 _ => {}
             }
         }
@@ -99,32 +316,19 @@ impl App {
     }
 
     fn handle_main_key_event(&mut self, key: KeyEvent) -> anyhow::Result<bool> {
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => Ok(true),
-            KeyCode::Char('o') => {
-                self.state = UiState::FilePrompt;
-                self.log = prompt_string(&self.state);
-                Ok(false)
-            }
-            KeyCode::Char('s') => {
-                self.state = UiState::SearchPrompt;
-                self.log = prompt_string(&self.state);
-                Ok(false)
-            }
-            KeyCode::Down => {
-                self.cur += 1;
-                self.log = "Got KeyCode Down".to_string();
-                Ok(false)
-            }
-            KeyCode::Up => {
-                if self.cur > 0 {
-                    self.cur -= 1
-                };
-                self.log = "Got KeyCode Up".to_string();
-                Ok(false)
-            }
-            x => {
-                self.log = format!("Got KeyCode {:?}", x);
+        // Some terminals (enhanced/kitty keyboard protocols) report SHIFT
+        // alongside the already-uppercased `Char`, e.g. 'N' as
+        // (Char('N'), SHIFT) instead of (Char('N'), NONE). The keymap is
+        // keyed on the char itself, so drop SHIFT before looking it up or
+        // shifted letters silently stop matching their binding.
+        let modifiers = match key.code {
+            KeyCode::Char(_) => key.modifiers.difference(KeyModifiers::SHIFT),
+            _ => key.modifiers,
+        };
+        match self.actions.get(&(key.code, modifiers)) {
+            Some(action) => action(self),
+            None => {
+                self.log = format!("Got KeyCode {:?}", key.code);
                 Ok(false)
             }
         }
@@ -134,59 +338,141 @@ impl App {
         match key.code {
             KeyCode::Esc => Ok(true),
             KeyCode::Enter if self.state == UiState::FilePrompt => {
-                self.filename.clear();
-                self.filename = self.tmpbuf.clone();
-                self.log = format!("Got: {}", self.tmpbuf);
-                self.tmpbuf.clear();
-                self.cur = 0;
-                let content = match fs::read_to_string(&self.filename) {
-                    Ok(txt) => txt,
-                    Err(e) => format!("ERROR: {:?}", e),
-                };
-                self.lines = count_newlines(&content);
-                self.content = content;
-                self.state = UiState::Main;
+                match self.input.submit() {
+                    Ok(filename) => {
+                        self.log = format!("Got: {}", filename);
+                        self.file_idx += 1;
+                        self.files.insert(self.file_idx, filename);
+                        self.load_current_file();
+                        self.state = UiState::Main;
+                    }
+                    Err(e) => self.log = e,
+                }
                 Ok(false)
             }
             KeyCode::Enter if self.state == UiState::SearchPrompt => {
-                self.search = self.tmpbuf.clone();
-                self.log = format!("Got: {}", self.tmpbuf);
-                self.tmpbuf.clear();
-                self.state = UiState::Main;
+                match self.input.submit() {
+                    Ok(pattern) => {
+                        self.search = pattern;
+                        self.state = UiState::Main;
+                        self.update_matches();
+                        self.current_match = 0;
+                        if self.matches.is_empty() {
+                            self.log = "pattern not found".to_string();
+                        } else {
+                            self.cur = self.matches[0].0;
+                            self.log = format!("match 1/{}", self.matches.len());
+                        }
+                    }
+                    Err(e) => self.log = e,
+                }
                 Ok(false)
             }
             KeyCode::Backspace => {
-                self.tmpbuf.pop();
-                self.log = format!("{}: {}", prompt_string(&self.state), self.tmpbuf);
+                self.input.backspace();
+                self.log = format!("{}{}", prompt_string(&self.state), self.input.value());
+                Ok(false)
+            }
+            KeyCode::Delete => {
+                self.input.delete();
+                self.log = format!("{}{}", prompt_string(&self.state), self.input.value());
+                Ok(false)
+            }
+            KeyCode::Left => {
+                self.input.move_left();
+                Ok(false)
+            }
+            KeyCode::Right => {
+                self.input.move_right();
                 Ok(false)
             }
             KeyCode::Char(c) => {
-                self.tmpbuf.push(c);
-                self.log = format!("{} {}", prompt_string(&self.state), self.tmpbuf);
+                self.input.insert_char(c);
+                self.log = format!("{}{}", prompt_string(&self.state), self.input.value());
                 Ok(false)
             }
             _x => Ok(false),
         }
     }
 
+    // Load `self.files[self.file_idx]` into `self.content`, resetting the
+    // viewport and any stale search state from the previous file.
+    fn load_current_file(&mut self) {
+        self.cur = 0;
+        let content = match load_rope(&self.files[self.file_idx]) {
+            Ok(rope) => rope,
+            Err(e) => Rope::from_str(&format!("ERROR: {:?}", e)),
+        };
+        self.lines = content_line_count(&content);
+        self.content = content;
+        self.update_matches();
+    }
+
+    // Recompute `self.matches` for the current `self.search` pattern,
+    // honouring `self.case_insensitive`. An empty pattern clears highlights.
+    fn update_matches(&mut self) {
+        self.matches.clear();
+        if self.search.is_empty() {
+            return;
+        }
+        for (line_idx, line) in self.content.lines().enumerate() {
+            let line = rope_line_to_string(line);
+            for (col, len) in find_line_matches(&line, &self.search, self.case_insensitive) {
+                self.matches.push((line_idx, col, len));
+            }
+        }
+    }
+
+    // Move `current_match` forward or backward through `self.matches`,
+    // per `self.search_direction`, wrapping around at either end, and jump
+    // `cur` to the new match.
+    fn advance_match(&mut self) {
+        if self.matches.is_empty() {
+            self.log = "pattern not found".to_string();
+            return;
+        }
+        self.current_match = match self.search_direction {
+            SearchDirection::Forward => (self.current_match + 1) % self.matches.len(),
+            SearchDirection::Backward => {
+                (self.current_match + self.matches.len() - 1) % self.matches.len()
+            }
+        };
+        self.cur = self.matches[self.current_match].0;
+        self.log = format!("match {}/{}", self.current_match + 1, self.matches.len());
+    }
+
     // Render the UI
     fn render_ui(&mut self) -> anyhow::Result<()> {
+        let mut info = RenderInfo::default();
         self.terminal.draw(|f| {
-            main_ui(
+            info = main_ui(
                 f,
                 &mut self.cur,
                 self.lines,
-                &self.content,
-                self.log.clone(),
+                RenderInput {
+                    content: &self.content,
+                    logtext: self.log.clone(),
+                    matches: &self.matches,
+                    show_gutter: self.show_gutter,
+                    filename: &self.files[self.file_idx],
+                    file_idx: self.file_idx,
+                    file_count: self.files.len(),
+                },
             )
         })?;
+        self.at_eof = info.at_eof;
+        self.height = info.height;
         Ok(())
     }
 
     fn setup_terminal() -> anyhow::Result<Terminal> {
         crossterm::terminal::enable_raw_mode()?;
         let mut stdout = std::io::stdout();
-        crossterm::execute!(stdout, crossterm::terminal::EnterAlternateScreen,)?;
+        crossterm::execute!(
+            stdout,
+            crossterm::terminal::EnterAlternateScreen,
+            EnableBracketedPaste,
+        )?;
         let backend = tui::backend::CrosstermBackend::new(stdout);
         let terminal = tui::Terminal::new(backend)?;
         Ok(terminal)
@@ -196,6 +482,7 @@ impl App {
         crossterm::terminal::disable_raw_mode()?;
         crossterm::execute!(
             self.terminal.backend_mut(),
+            DisableBracketedPaste,
             crossterm::terminal::LeaveAlternateScreen,
         )?;
         self.terminal.show_cursor()?;
@@ -203,20 +490,52 @@ impl App {
     }
 }
 
+// The render-time inputs `main_ui` needs beyond the scroll position and
+// total line count, bundled so growing the UI doesn't grow its parameter
+// list.
+struct RenderInput<'a> {
+    content: &'a Rope,
+    logtext: String,
+    matches: &'a [(usize, usize, usize)],
+    show_gutter: bool,
+    filename: &'a str,
+    file_idx: usize,
+    file_count: usize,
+}
+
+// What the caller needs back after a render: whether the viewport is
+// showing the end of the file, and how many rows of text the content frame
+// shows inside its borders.
+#[derive(Default)]
+struct RenderInfo {
+    at_eof: bool,
+    height: usize,
+}
+
 fn main_ui<B: Backend>(
     f: &mut Frame<B>,
     cur_pos: &mut usize,
     lines: usize,
-    content: &String,
-    logtext: String,
-) {
+    input: RenderInput,
+) -> RenderInfo {
+    let RenderInput {
+        content,
+        logtext,
+        matches,
+        show_gutter,
+        filename,
+        file_idx,
+        file_count,
+    } = input;
+
     //
     // Create the Layout of the UI.
     //
-    // We have three parts:
+    // We have four parts:
     //  - a frame with a help text for displaying the commands that can be used
     //  - a frame where the file content is shown
     //  - a frame where various internal log info is shown
+    //  - a one-row status line with the scroll position, like `less`
     //
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -225,7 +544,8 @@ fn main_ui<B: Backend>(
             [
                 Constraint::Percentage(10),
                 Constraint::Percentage(80),
-                Constraint::Percentage(10),
+                Constraint::Percentage(7),
+                Constraint::Length(1),
             ]
             .as_ref(),
         )
@@ -234,7 +554,9 @@ fn main_ui<B: Backend>(
     //
     // Help frame
     //
-    let helptext = "Quit=q/Esq , Scroll=Up/Down , OpenFile:o , Search:s";
+    let helptext = "Quit=q/Esq , Scroll=Up/Down , Top/Bottom:g/G , HalfPage=Ctrl-d/Ctrl-u , \
+Page=PgDn/PgUp , OpenFile:o , Search:s , Next/Prev match:n/N , CaseInsensitive:i , \
+LineNumbers:l , Next/Prev file:]/[ , QuitAtEOF:e";
     let help = Paragraph::new(helptext)
         .block(Block::default().title("Help").borders(Borders::ALL))
         .style(Style::default().fg(Color::White).bg(Color::Black))
@@ -245,11 +567,8 @@ fn main_ui<B: Backend>(
     //
     // File content frame
     //
-    let v: Vec<&str> = content.lines().collect();
-
-    // Calculate the max amount of scrolling to be done
-    // with respect to the number of lines and the amount
-    // of lines displayed.
+    // Only the lines that are actually visible are ever materialized, so
+    // rendering cost no longer grows with the size of the file.
     let height = chunks[1].height as usize;
     let max_pos = if lines <= height {
         // The whole file is contained within the Frame.
@@ -259,19 +578,62 @@ fn main_ui<B: Backend>(
         // the file is at the bottom of the Frame.
         cmp::min(lines - height + 2_usize, *cur_pos)
     };
+    // On a very short terminal (height <= 1) the clamp above can still land
+    // past the last line, and `Rope::lines_at` panics if asked to start
+    // beyond `len_lines()`, so pin it to a valid line index.
+    let max_pos = cmp::min(max_pos, content.len_lines());
     // Adjust cur_pos accordingly.
     *cur_pos = max_pos;
 
-    let text: Vec<Spans> = (&v[max_pos..])
-        .iter()
-        .map(|line| Spans::from(*line))
+    // Width of the line-number gutter, computed from the total line count.
+    let gutter_digits = (lines.max(1)).ilog10() as usize + 1;
+
+    let text: Vec<Spans> = content
+        .lines_at(max_pos)
+        .take(height)
+        .enumerate()
+        .map(|(row, line)| {
+            let line_idx = max_pos + row;
+            let line = rope_line_to_string(line);
+            Spans::from(highlight_line(&line, line_idx, matches).0)
+        })
         .collect();
+    let title = format!("{} ({}/{})", filename, file_idx + 1, file_count);
+    let block = Block::default().title(title).borders(Borders::ALL);
+    // The gutter is rendered in its own layout column, to the left of the
+    // text, so `Wrap` only ever re-flows the text column: a wrapped
+    // continuation line can't drift under the line numbers.
+    let inner = block.inner(chunks[1]);
+    f.render_widget(block, chunks[1]);
+    let content_area = if show_gutter {
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(gutter_digits as u16 + 1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+        let gutter_lines: Vec<Spans> = (0..text.len())
+            .map(|row| {
+                Spans::from(Span::styled(
+                    format!("{:>width$}", max_pos + row + 1, width = gutter_digits),
+                    Style::default().fg(Color::DarkGray),
+                ))
+            })
+            .collect();
+        let gutter = Paragraph::new(gutter_lines)
+            .style(Style::default().fg(Color::White).bg(Color::Black))
+            .alignment(Alignment::Left);
+        f.render_widget(gutter, cols[0]);
+        cols[1]
+    } else {
+        inner
+    };
     let para = Paragraph::new(text)
-        .block(Block::default().title("File Content").borders(Borders::ALL))
         .style(Style::default().fg(Color::White).bg(Color::Black))
         .alignment(Alignment::Left)
         .wrap(Wrap { trim: true });
-    f.render_widget(para, chunks[1]);
+    f.render_widget(para, content_area);
 
     //
     // Log frame
@@ -283,6 +645,40 @@ fn main_ui<B: Backend>(
         .alignment(Alignment::Left)
         .wrap(Wrap { trim: true });
     f.render_widget(log, chunks[2]);
+
+    //
+    // Status line: filename, first visible line and scroll percentage,
+    // mirroring `less`'s bottom prompt.
+    //
+    // Rows of text inside the content block's top and bottom borders.
+    let visible = height.saturating_sub(2);
+    let at_eof = max_pos + visible >= lines;
+    let pct = if lines == 0 {
+        100
+    } else {
+        cmp::min(max_pos + visible, lines) * 100 / lines
+    };
+    let left = format!("{} line {}", filename, max_pos + 1);
+    let right = if at_eof {
+        "(END)".to_string()
+    } else {
+        format!("{}%", pct)
+    };
+    let width = chunks[3].width as usize;
+    let pad = width
+        .saturating_sub(left.len())
+        .saturating_sub(right.len())
+        .max(1);
+    let status_text = format!("{}{}{}", left, " ".repeat(pad), right);
+    let status = Paragraph::new(status_text)
+        .style(Style::default().fg(Color::Black).bg(Color::White))
+        .alignment(Alignment::Left);
+    f.render_widget(status, chunks[3]);
+
+    RenderInfo {
+        at_eof,
+        height: visible,
+    }
 }
 
 impl Drop for App {
@@ -291,6 +687,135 @@ impl Drop for App {
     }
 }
 
-fn count_newlines(s: &str) -> usize {
-    s.as_bytes().iter().filter(|&&c| c == b'\n').count()
+// Load `filename` into a Rope without ever materializing the whole file as
+// a single String.
+fn load_rope(filename: &str) -> io::Result<Rope> {
+    let file = fs::File::open(filename)?;
+    Rope::from_reader(BufReader::new(file))
+}
+
+// The number of real lines in `content`. `Rope::len_lines()` counts a
+// phantom trailing empty line whenever the text ends in `\n` (ropey treats
+// "a\n" as the two lines "a\n" and ""), which isn't a line `less` would
+// show, so the scroll/percentage/EOF math would otherwise be off by one.
+fn content_line_count(content: &Rope) -> usize {
+    let lines = content.len_lines();
+    if lines > 0 && content.line(lines - 1).len_chars() == 0 {
+        lines - 1
+    } else {
+        lines
+    }
+}
+
+// A line as yielded by `Rope::lines`/`lines_at` includes its trailing
+// line terminator; strip it so it behaves like `str::lines`.
+fn rope_line_to_string(line: ropey::RopeSlice) -> String {
+    let mut s = line.to_string();
+    if s.ends_with('\n') {
+        s.pop();
+        if s.ends_with('\r') {
+            s.pop();
+        }
+    }
+    s
+}
+
+// Find every occurrence of `pattern` in `line`, honouring `case_insensitive`,
+// and return (byte_start, byte_len) pairs that are always valid byte ranges
+// into the *original* `line`. Folding a character to lowercase can change
+// its byte length (e.g. U+0130 'İ' folds from 2 bytes to 3), so matching is
+// done against a folded copy of the line while remembering, byte-for-byte,
+// which original char produced each folded byte; that mapping is then used
+// to translate a match back into original-line coordinates instead of
+// reusing the folded offsets directly.
+fn find_line_matches(line: &str, pattern: &str, case_insensitive: bool) -> Vec<(usize, usize)> {
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+    if !case_insensitive {
+        let mut result = Vec::new();
+        let mut start = 0;
+        while let Some(found) = line[start..].find(pattern) {
+            let col = start + found;
+            result.push((col, pattern.len()));
+            start = col + pattern.len();
+            if start > line.len() {
+                break;
+            }
+        }
+        return result;
+    }
+
+    let mut folded = String::new();
+    let mut orig_start = Vec::new();
+    let mut orig_end = Vec::new();
+    for (start, ch) in line.char_indices() {
+        let end = start + ch.len_utf8();
+        let mut buf = [0u8; 4];
+        for lc in ch.to_lowercase() {
+            let s = lc.encode_utf8(&mut buf);
+            folded.push_str(s);
+            for _ in 0..s.len() {
+                orig_start.push(start);
+                orig_end.push(end);
+            }
+        }
+    }
+    let pattern = pattern.to_lowercase();
+    let mut result = Vec::new();
+    let mut start = 0;
+    while let Some(found) = folded[start..].find(&pattern) {
+        let col = start + found;
+        let col_end = col + pattern.len();
+        let o_start = orig_start[col];
+        let o_end = orig_end[col_end - 1];
+        result.push((o_start, o_end - o_start));
+        start = col_end;
+        if start > folded.len() {
+            break;
+        }
+    }
+    result
+}
+
+// Split `line` into Spans, giving every matching run on this line the
+// highlight style and leaving the rest at the default style.
+fn highlight_line(
+    line: &str,
+    line_idx: usize,
+    matches: &[(usize, usize, usize)],
+) -> Spans<'static> {
+    let runs: Vec<(usize, usize)> = matches
+        .iter()
+        .filter(|(l, _, _)| *l == line_idx)
+        .map(|(_, col, len)| (*col, *len))
+        .collect();
+    if runs.is_empty() {
+        return Spans::from(line.to_string());
+    }
+
+    let highlight_style = Style::default().fg(Color::Black).bg(Color::Yellow);
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    for (col, len) in runs {
+        let end = col + len;
+        if col < pos
+            || end > line.len()
+            || !line.is_char_boundary(col)
+            || !line.is_char_boundary(end)
+        {
+            // Overlapping, out-of-range, or (defensively) a non-boundary
+            // match, skip it rather than panic on a slice.
+            continue;
+        }
+        if col > pos {
+            spans.push(Span::raw(line[pos..col].to_string()));
+        }
+        spans.push(Span::styled(line[col..end].to_string(), highlight_style));
+        pos = end;
+    }
+    if pos < line.len() {
+        spans.push(Span::raw(line[pos..].to_string()));
+    }
+    Spans::from(spans)
 }
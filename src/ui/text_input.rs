@@ -0,0 +1,115 @@
+// A small reusable single-line text input: owns its buffer and cursor,
+// optionally filters out unwanted characters as they are typed, and runs a
+// validator on submit so the caller can keep the prompt open and show why
+// the input was rejected.
+
+pub struct TextInput {
+    buffer: String,
+    cursor: usize, // index in chars, not bytes
+    filter_map_char: Option<Box<dyn Fn(char) -> Option<char>>>,
+    validate: Option<Box<dyn Fn(&str) -> Result<(), String>>>,
+}
+
+impl Default for TextInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self {
+            buffer: String::new(),
+            cursor: 0,
+            filter_map_char: None,
+            validate: None,
+        }
+    }
+
+    pub fn with_filter(mut self, f: impl Fn(char) -> Option<char> + 'static) -> Self {
+        self.filter_map_char = Some(Box::new(f));
+        self
+    }
+
+    pub fn with_validate(mut self, f: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+        self.validate = Some(Box::new(f));
+        self
+    }
+
+    pub fn value(&self) -> &str {
+        &self.buffer
+    }
+
+    // Insert `c` at the cursor, dropping it if `filter_map_char` rejects it.
+    pub fn insert_char(&mut self, c: char) {
+        let c = match &self.filter_map_char {
+            Some(f) => match f(c) {
+                Some(c) => c,
+                None => return,
+            },
+            None => c,
+        };
+        let byte_idx = self.byte_index(self.cursor);
+        self.buffer.insert(byte_idx, c);
+        self.cursor += 1;
+    }
+
+    // Insert a whole string at once, e.g. a bracketed paste, one char at a
+    // time so the same filter applies to every character.
+    pub fn insert_str(&mut self, s: &str) {
+        for c in s.chars() {
+            self.insert_char(c);
+        }
+    }
+
+    // Delete the char before the cursor.
+    pub fn backspace(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        let start = self.byte_index(self.cursor - 1);
+        let end = self.byte_index(self.cursor);
+        self.buffer.replace_range(start..end, "");
+        self.cursor -= 1;
+    }
+
+    // Delete the char at the cursor.
+    pub fn delete(&mut self) {
+        let len = self.buffer.chars().count();
+        if self.cursor >= len {
+            return;
+        }
+        let start = self.byte_index(self.cursor);
+        let end = self.byte_index(self.cursor + 1);
+        self.buffer.replace_range(start..end, "");
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        let len = self.buffer.chars().count();
+        if self.cursor < len {
+            self.cursor += 1;
+        }
+    }
+
+    // Run the validator (if any) against the current buffer. On success
+    // returns the submitted text; on failure the input is left untouched so
+    // the prompt can stay open and show the error.
+    pub fn submit(&self) -> Result<String, String> {
+        match &self.validate {
+            Some(v) => v(&self.buffer).map(|_| self.buffer.clone()),
+            None => Ok(self.buffer.clone()),
+        }
+    }
+
+    fn byte_index(&self, char_idx: usize) -> usize {
+        self.buffer
+            .char_indices()
+            .nth(char_idx)
+            .map(|(b, _)| b)
+            .unwrap_or(self.buffer.len())
+    }
+}